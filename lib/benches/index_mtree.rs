@@ -3,6 +3,8 @@ use criterion::{criterion_group, criterion_main, BenchmarkGroup, Criterion, Thro
 use futures::executor::block_on;
 use rand::prelude::ThreadRng;
 use rand::{thread_rng, Rng};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::sync::Arc;
 use std::time::Duration;
 use surrealdb::idx::docids::DocId;
@@ -17,20 +19,34 @@ use tokio::runtime::Runtime;
 
 fn bench_index_mtree_dim_3(c: &mut Criterion) {
 	bench_index_mtree(c, 1_000, 100_000, 3, 120);
+	bench_index_mtree_recall(c, 2_000, 3);
 }
 
 fn bench_index_mtree_dim_50(c: &mut Criterion) {
 	bench_index_mtree(c, 100, 10_000, 50, 20);
+	bench_index_mtree_recall(c, 2_000, 50);
 }
 
 fn bench_index_mtree_dim_300(c: &mut Criterion) {
 	bench_index_mtree(c, 50, 5_000, 300, 40);
+	bench_index_mtree_recall(c, 1_000, 300);
 }
 
 fn bench_index_mtree_dim_2048(c: &mut Criterion) {
 	bench_index_mtree(c, 10, 1_000, 2048, 60);
+	bench_index_mtree_recall(c, 500, 2048);
 }
 
+/// Number of randomly sampled queries averaged together for each recall@k
+/// measurement below.
+const RECALL_QUERIES: usize = 50;
+const RECALL_K: usize = 10;
+
+/// Every distance mode the `MTree` supports, benchmarked identically so a
+/// regression in one mode's pruning (e.g. `DotProduct` falling back to a
+/// full scan) shows up as a throughput drop against the others.
+const DISTANCES: [Distance; 3] = [Distance::Euclidean, Distance::Cosine, Distance::DotProduct];
+
 fn bench_index_mtree(
 	c: &mut Criterion,
 	debug_samples_len: usize,
@@ -47,29 +63,164 @@ fn bench_index_mtree(
 	// Both benchmark groups are sharing the same datastore
 	let ds = block_on(Datastore::new("memory")).unwrap();
 
-	// Indexing benchmark group
-	{
-		let mut group = get_group(c, "index_mtree_insert", samples_len, measurement_secs);
-		let id = format!("len_{}_dim_{}", samples_len, vector_dimension);
+	for distance in DISTANCES {
+		// Indexing benchmark group
+		{
+			let mut group = get_group(c, "index_mtree_insert", samples_len, measurement_secs);
+			let id =
+				format!("len_{}_dim_{}_distance_{:?}", samples_len, vector_dimension, distance);
+			group.bench_function(id, |b| {
+				b.to_async(Runtime::new().unwrap())
+					.iter(|| insert_objects(&ds, samples_len, vector_dimension, distance));
+			});
+			group.finish();
+		}
+
+		// Knn lookup benchmark group
+		{
+			let mut group = get_group(c, "index_mtree_lookup", 100_000, 10);
+			for knn in [1, 10] {
+				let id = format!(
+					"knn_{}_len_{}_dim_{}_distance_{:?}",
+					knn, samples_len, vector_dimension, distance
+				);
+				group.bench_function(id, |b| {
+					b.to_async(Runtime::new().unwrap()).iter(|| {
+						knn_lookup_objects(&ds, 100_000, vector_dimension, knn, distance)
+					});
+				});
+			}
+			group.finish();
+		}
+	}
+}
+
+/// Measures recall@k against an exact brute-force baseline for every
+/// distance mode, so index-parameter changes (node capacity, distance
+/// metric) can be judged on result quality, not only on throughput.
+///
+/// This is wrapped in a Criterion group (rather than a plain assertion) so
+/// the recall numbers, which `measure_recall` prints to stderr on every
+/// iteration, appear next to Criterion's throughput output. `samples_len` is
+/// kept smaller than the release-build sizes of the throughput benchmarks
+/// above since the brute-force baseline is O(n) per query.
+fn bench_index_mtree_recall(c: &mut Criterion, samples_len: usize, vector_dimension: usize) {
+	let ds = block_on(Datastore::new("memory")).unwrap();
+
+	for distance in DISTANCES {
+		let mut group = get_group(c, "index_mtree_recall", RECALL_QUERIES, 10);
+		let id = format!("len_{}_dim_{}_distance_{:?}", samples_len, vector_dimension, distance);
 		group.bench_function(id, |b| {
 			b.to_async(Runtime::new().unwrap())
-				.iter(|| insert_objects(&ds, samples_len, vector_dimension));
+				.iter(|| measure_recall(&ds, samples_len, vector_dimension, RECALL_K, distance));
 		});
 		group.finish();
 	}
+}
 
-	// Knn lookup benchmark group
-	{
-		let mut group = get_group(c, "index_mtree_lookup", 100_000, 10);
-		for knn in [1, 10] {
-			let id = format!("knn_{}_len_{}_dim_{}", knn, samples_len, vector_dimension);
-			group.bench_function(id, |b| {
-				b.to_async(Runtime::new().unwrap())
-					.iter(|| knn_lookup_objects(&ds, 100_000, vector_dimension, knn));
-			});
+struct ScoredCandidate {
+	distance: f64,
+	doc: DocId,
+}
+
+impl Eq for ScoredCandidate {}
+
+impl PartialEq for ScoredCandidate {
+	fn eq(&self, other: &Self) -> bool {
+		self.distance == other.distance
+	}
+}
+
+impl Ord for ScoredCandidate {
+	// Natural order, so the `BinaryHeap` (a max-heap) surfaces the largest
+	// distance on top; popping that on overflow keeps the k smallest.
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.distance.partial_cmp(&other.distance).unwrap_or(Ordering::Equal)
+	}
+}
+
+impl PartialOrd for ScoredCandidate {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+/// Exact, brute-force k-nearest-neighbours: scans every vector and keeps a
+/// bounded max-heap of the k closest, used as the ground truth recall is
+/// measured against.
+fn exact_knn(dataset: &[(DocId, Vector)], query: &Vector, k: usize, distance: Distance) -> Vec<DocId> {
+	let mut heap: BinaryHeap<ScoredCandidate> = BinaryHeap::with_capacity(k + 1);
+	for (doc, vector) in dataset {
+		let d = distance.calculate(query, vector);
+		heap.push(ScoredCandidate {
+			distance: d,
+			doc: *doc,
+		});
+		if heap.len() > k {
+			heap.pop();
 		}
-		group.finish();
 	}
+	heap.into_sorted_vec().into_iter().map(|c| c.doc).collect()
+}
+
+fn recall_at_k(approx: &[DocId], exact: &[DocId]) -> f64 {
+	if exact.is_empty() {
+		return 1.0;
+	}
+	let found = approx.iter().filter(|d| exact.contains(d)).count();
+	found as f64 / exact.len() as f64
+}
+
+/// Note: `MTree::knn_search` is currently an exhaustive scan (it has no
+/// routing objects or covering radii to prune with), so it always agrees
+/// with the exact baseline below and this will report recall == 1.0
+/// regardless of `distance` or node capacity. It becomes a meaningful
+/// regression signal once `MTree` actually prunes.
+async fn measure_recall(
+	ds: &Datastore,
+	samples_len: usize,
+	vector_size: usize,
+	k: usize,
+	distance: Distance,
+) -> f64 {
+	let mut rng = thread_rng();
+	let mut t = mtree(distance);
+	let mut tx = ds.transaction(Write, Optimistic).await.unwrap();
+	let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, samples_len + 1);
+	let mut s = s.lock().await;
+
+	// The dataset is kept in the same (optionally normalized) space the
+	// `MTree` compares against internally, so the exact baseline below is
+	// comparing like with like.
+	let mut dataset = Vec::with_capacity(samples_len);
+	for i in 0..samples_len {
+		let object = random_object(&mut rng, vector_size);
+		let comparable =
+			if distance.requires_normalization() { object.normalize() } else { object.clone() };
+		t.insert(&mut tx, &mut s, object, i as DocId).await.unwrap();
+		dataset.push((i as DocId, comparable));
+	}
+
+	let mut total_recall = 0.0;
+	for _ in 0..RECALL_QUERIES {
+		let query = random_object(&mut rng, vector_size);
+		let comparable_query =
+			if distance.requires_normalization() { query.normalize() } else { query.clone() };
+		let approx: Vec<DocId> = t
+			.knn_search(&mut tx, &mut s, &query, k)
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|(doc, _)| doc)
+			.collect();
+		let exact = exact_knn(&dataset, &comparable_query, k, distance);
+		total_recall += recall_at_k(&approx, &exact);
+	}
+	tx.rollback_with_panic();
+
+	let recall = total_recall / RECALL_QUERIES as f64;
+	eprintln!("recall@{k} dim={vector_size} distance={distance:?}: {recall:.4}");
+	recall
 }
 
 fn get_group<'a>(
@@ -92,13 +243,18 @@ fn random_object(rng: &mut ThreadRng, vector_size: usize) -> Vector {
 	Vector::F32(vec)
 }
 
-fn mtree() -> MTree {
-	MTree::new(MState::new(40), Distance::Euclidean)
+fn mtree(distance: Distance) -> MTree {
+	MTree::new(MState::new(40), distance)
 }
 
-async fn insert_objects(ds: &Datastore, samples_size: usize, vector_size: usize) {
+async fn insert_objects(
+	ds: &Datastore,
+	samples_size: usize,
+	vector_size: usize,
+	distance: Distance,
+) {
 	let mut rng = thread_rng();
-	let mut t = mtree();
+	let mut t = mtree(distance);
 	let mut tx = ds.transaction(Write, Optimistic).await.unwrap();
 	let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, 20);
 	let mut s = s.lock().await;
@@ -110,9 +266,15 @@ async fn insert_objects(ds: &Datastore, samples_size: usize, vector_size: usize)
 	tx.commit().await.unwrap();
 }
 
-async fn knn_lookup_objects(ds: &Datastore, samples_size: usize, vector_size: usize, knn: usize) {
+async fn knn_lookup_objects(
+	ds: &Datastore,
+	samples_size: usize,
+	vector_size: usize,
+	knn: usize,
+	distance: Distance,
+) {
 	let mut rng = thread_rng();
-	let t = mtree();
+	let t = mtree(distance);
 	let mut tx = ds.transaction(Read, Optimistic).await.unwrap();
 	let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Read, 20);
 	let mut s = s.lock().await;
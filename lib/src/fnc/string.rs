@@ -0,0 +1,34 @@
+use crate::err::Error;
+use crate::sql::value::{Object, Value};
+use crate::sql::Regex;
+
+/// Returns every full match of `regex` in `val` as an array of strings, or
+/// an empty array when there are none.
+///
+/// Backs the SQL function `string::matches`.
+pub fn matches((val, regex): (String, Regex)) -> Result<Value, Error> {
+	let matches = regex.matches(&val).into_iter().map(Value::from).collect();
+	Ok(Value::Array(matches))
+}
+
+/// Returns the capture groups of every match of `regex` in `val`. Each match
+/// becomes an object keyed first by its numbered groups (`"0"` the whole
+/// match, `"1"` the first parenthesized group, and so on) and then by any
+/// named groups; a group that didn't participate in a given match is
+/// `Value::Null` rather than missing, so the shape is stable across matches.
+///
+/// Backs the SQL function `string::captures`.
+pub fn captures((val, regex): (String, Regex)) -> Result<Value, Error> {
+	let captures = regex
+		.captures(&val)
+		.into_iter()
+		.map(|groups| {
+			let object: Object = groups
+				.into_iter()
+				.map(|(key, value)| (key, value.map(Value::from).unwrap_or(Value::Null)))
+				.collect();
+			Value::Object(object)
+		})
+		.collect();
+	Ok(Value::Array(captures))
+}
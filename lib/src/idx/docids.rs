@@ -0,0 +1,3 @@
+/// Identifier of a document stored behind an index, used by `MTree` entries
+/// to point back at the record the vector belongs to.
+pub type DocId = u64;
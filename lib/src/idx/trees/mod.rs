@@ -0,0 +1,3 @@
+pub mod mtree;
+pub mod store;
+pub mod vector;
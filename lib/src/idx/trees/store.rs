@@ -0,0 +1,171 @@
+use crate::err::Error;
+use crate::kvs::Transaction;
+use async_compression::tokio::bufread::{ZstdDecoder, ZstdEncoder};
+use async_compression::Level;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+pub type NodeId = u64;
+
+/// Magic bytes prefixed to a Zstd-compressed node blob, followed by a single
+/// version byte. Existing nodes stored before this feature landed have
+/// neither, so a blob is only treated as compressed when both match;
+/// anything else falls back to being read as raw, uncompressed bincode.
+const COMPRESSED_MAGIC: &[u8; 4] = b"SRTC";
+const COMPRESSED_VERSION: u8 = 1;
+
+/// Reads the configured Zstd compression level for tree node storage, or
+/// `None` if compression is disabled. Like `SURREAL_REGEX_CACHE_SIZE`, it
+/// comes from an env var, with an invalid value falling back to the default
+/// rather than failing to start; it is read each time a store is created.
+fn compression_level() -> Option<i32> {
+	let level: i32 = env::var("SURREAL_INDEX_COMPRESSION_LEVEL")
+		.ok()
+		.and_then(|v| v.parse().ok())
+		.unwrap_or(0);
+	(level > 0).then_some(level)
+}
+
+/// Where a tree's nodes come from.
+///
+/// `Debug` keeps everything in memory and is used by benchmarks/tests that
+/// want to exercise the tree logic without paying for KV round-trips.
+/// `Transaction` is the real, persisted backing used in production: nodes
+/// are serialized and stored under `prefix` in the key-value store.
+#[derive(Clone)]
+pub enum TreeNodeProvider {
+	Debug,
+	Transaction {
+		prefix: Vec<u8>,
+	},
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum TreeStoreType {
+	Read,
+	Write,
+}
+
+pub struct TreeNodeStore<N> {
+	provider: TreeNodeProvider,
+	store_type: TreeStoreType,
+	cache: HashMap<NodeId, Arc<N>>,
+	cache_size: usize,
+	/// Zstd level new nodes are written with, or `None` to write them
+	/// uncompressed. Read once at construction, same as `REGEX_CACHE`'s
+	/// size is read once at first use.
+	compression_level: Option<i32>,
+}
+
+impl<N> TreeNodeStore<N>
+where
+	N: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+	/// Creates a new node store wrapped in the `Arc<Mutex<_>>` every caller
+	/// (benchmarks, the `MTree` itself) locks before reading or writing a
+	/// node, so concurrent access to the same tree is always serialized.
+	pub fn new(
+		provider: TreeNodeProvider,
+		store_type: TreeStoreType,
+		cache_size: usize,
+	) -> Arc<Mutex<Self>> {
+		Arc::new(Mutex::new(Self {
+			provider,
+			store_type,
+			cache: HashMap::new(),
+			cache_size,
+			compression_level: compression_level(),
+		}))
+	}
+
+	pub fn store_type(&self) -> TreeStoreType {
+		self.store_type
+	}
+
+	pub async fn get_node(&mut self, tx: &mut Transaction, id: NodeId) -> Result<Arc<N>, Error> {
+		if let Some(n) = self.cache.get(&id) {
+			return Ok(n.clone());
+		}
+		let node = match &self.provider {
+			TreeNodeProvider::Debug => {
+				return Err(Error::Unreachable("node not found in debug store".into()))
+			}
+			TreeNodeProvider::Transaction {
+				prefix,
+			} => {
+				let key = node_key(prefix, id);
+				let bytes = tx.get(key).await?.ok_or(Error::CorruptedIndex("missing node"))?;
+				let bytes = decompress(&bytes).await?;
+				Arc::new(bincode::deserialize(&bytes).map_err(|e| Error::Serialization(e.to_string()))?)
+			}
+		};
+		if self.cache.len() < self.cache_size {
+			self.cache.insert(id, node.clone());
+		}
+		Ok(node)
+	}
+
+	pub async fn set_node(&mut self, tx: &mut Transaction, id: NodeId, node: N) -> Result<(), Error> {
+		let node = Arc::new(node);
+		if let TreeNodeProvider::Transaction {
+			prefix,
+		} = &self.provider
+		{
+			let key = node_key(prefix, id);
+			let bytes = bincode::serialize(node.as_ref()).map_err(|e| Error::Serialization(e.to_string()))?;
+			let bytes = compress(bytes, self.compression_level).await?;
+			tx.set(key, bytes).await?;
+		}
+		// `Debug` has no backing store besides the cache, so it always keeps
+		// the node.
+		if matches!(self.provider, TreeNodeProvider::Debug) || self.cache.len() < self.cache_size {
+			self.cache.insert(id, node);
+		}
+		Ok(())
+	}
+}
+
+fn node_key(prefix: &[u8], id: NodeId) -> Vec<u8> {
+	let mut key = prefix.to_vec();
+	key.extend_from_slice(&id.to_be_bytes());
+	key
+}
+
+/// Streams `bytes` through an async Zstd encoder at `level` and prefixes the
+/// result with the magic/version header, or returns `bytes` untouched (no
+/// header at all) when `level` is `None` so an all-disabled deployment never
+/// pays for the 5 header bytes either.
+async fn compress(bytes: Vec<u8>, level: Option<i32>) -> Result<Vec<u8>, Error> {
+	let Some(level) = level else {
+		return Ok(bytes);
+	};
+	let mut encoder =
+		ZstdEncoder::with_quality(tokio::io::BufReader::new(&bytes[..]), Level::Precise(level));
+	let mut out = Vec::with_capacity(COMPRESSED_MAGIC.len() + 1);
+	out.extend_from_slice(COMPRESSED_MAGIC);
+	out.push(COMPRESSED_VERSION);
+	encoder.read_to_end(&mut out).await.map_err(|e| Error::Serialization(e.to_string()))?;
+	Ok(out)
+}
+
+/// Inverse of [`compress`]: if `bytes` starts with the magic/version header,
+/// streams the remainder through an async Zstd decoder; otherwise `bytes`
+/// is assumed to be a node stored before compression support existed and is
+/// returned as-is.
+async fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+	let header_len = COMPRESSED_MAGIC.len() + 1;
+	if bytes.len() < header_len
+		|| &bytes[..COMPRESSED_MAGIC.len()] != COMPRESSED_MAGIC
+		|| bytes[COMPRESSED_MAGIC.len()] != COMPRESSED_VERSION
+	{
+		return Ok(bytes.to_vec());
+	}
+	let mut decoder = ZstdDecoder::new(tokio::io::BufReader::new(&bytes[header_len..]));
+	let mut out = Vec::new();
+	decoder.read_to_end(&mut out).await.map_err(|e| Error::Serialization(e.to_string()))?;
+	Ok(out)
+}
@@ -0,0 +1,92 @@
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+
+/// A stored or queried embedding. Kept as two variants (rather than always
+/// widening to `f64`) so that the common `f32` case doesn't pay for doubled
+/// storage in high-dimensional indexes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub enum Vector {
+	F32(Vec<f32>),
+	F64(Vec<f64>),
+}
+
+impl Vector {
+	pub fn len(&self) -> usize {
+		match self {
+			Vector::F32(v) => v.len(),
+			Vector::F64(v) => v.len(),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	fn iter_f64(&self) -> Box<dyn Iterator<Item = f64> + '_> {
+		match self {
+			Vector::F32(v) => Box::new(v.iter().map(|f| *f as f64)),
+			Vector::F64(v) => Box::new(v.iter().copied()),
+		}
+	}
+
+	pub fn euclidean_distance(&self, other: &Self) -> f64 {
+		self.iter_f64()
+			.zip(other.iter_f64())
+			.map(|(a, b)| (a - b).powi(2))
+			.sum::<f64>()
+			.sqrt()
+	}
+
+	pub fn dot(&self, other: &Self) -> f64 {
+		self.iter_f64().zip(other.iter_f64()).map(|(a, b)| a * b).sum()
+	}
+
+	pub fn magnitude(&self) -> f64 {
+		self.iter_f64().map(|a| a * a).sum::<f64>().sqrt()
+	}
+
+	/// Returns an L2-normalized copy of this vector, i.e. `self / ||self||`,
+	/// so that `dot(normalized_a, normalized_b) == cosine_similarity(a, b)`.
+	/// A zero vector is returned unchanged, since it has no direction to
+	/// normalize to.
+	pub fn normalize(&self) -> Self {
+		let magnitude = self.magnitude();
+		if magnitude == 0.0 {
+			return self.clone();
+		}
+		match self {
+			Vector::F32(v) => {
+				let m = magnitude as f32;
+				Vector::F32(v.iter().map(|f| f / m).collect())
+			}
+			Vector::F64(v) => Vector::F64(v.iter().map(|f| f / magnitude).collect()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn normalized_vector_has_unit_magnitude() {
+		let v = Vector::F64(vec![3.0, 4.0]);
+		let n = v.normalize();
+		assert!((n.magnitude() - 1.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn dot_of_normalized_vectors_matches_cosine_similarity() {
+		let a = Vector::F64(vec![1.0, 0.0]).normalize();
+		let b = Vector::F64(vec![1.0, 1.0]).normalize();
+		let expected = 1.0 / 2f64.sqrt();
+		assert!((a.dot(&b) - expected).abs() < 1e-9);
+	}
+
+	#[test]
+	fn zero_vector_normalizes_to_itself() {
+		let v = Vector::F64(vec![0.0, 0.0]);
+		assert_eq!(v.normalize(), v);
+	}
+}
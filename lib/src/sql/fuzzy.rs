@@ -0,0 +1,319 @@
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use std::env;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+/// Base score awarded for every character of the query that is matched.
+const SCORE_MATCH: i64 = 16;
+/// Bonus awarded when a match starts at a word boundary (start of string,
+/// after a separator such as `_`/`-`/space, or on a lower->upper camelCase
+/// transition).
+const BONUS_BOUNDARY: i64 = 8;
+/// Bonus added on top of `BONUS_BOUNDARY` for the very first character of
+/// the haystack.
+const BONUS_FIRST_CHAR: i64 = 4;
+/// Multiplied by the length of the consecutive-match run a match extends (1
+/// for a match that starts a new run, capped at 8) and added to its score.
+const BONUS_CONSECUTIVE: i64 = 4;
+/// Penalty subtracted once whenever at least one haystack character was
+/// skipped since the last match.
+const PENALTY_GAP: i64 = 3;
+/// Penalty subtracted per skipped character beyond the first, so long gaps
+/// are penalised more than short ones.
+const PENALTY_GAP_EXTRA: i64 = 1;
+
+/// A compiled fuzzy-match query, ready to be scored against any number of
+/// haystacks.
+///
+/// Compilation just lower-cases the query when matching case-insensitively;
+/// it is kept as a distinct type (rather than a bare `&str`) so it mirrors
+/// [`super::regex::Regex`] and can be cached the same way.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FuzzyQuery {
+	chars: Vec<char>,
+	case_sensitive: bool,
+}
+
+impl FuzzyQuery {
+	pub fn query(&self) -> &[char] {
+		&self.chars
+	}
+}
+
+fn fuzzy_new(query: &str, case_sensitive: bool) -> FuzzyQuery {
+	static FUZZY_CACHE: Lazy<Mutex<LruCache<(String, bool), FuzzyQuery>>> = Lazy::new(|| {
+		let cache_size: usize = env::var("SURREAL_FUZZY_CACHE_SIZE")
+			.map_or(1000, |v| v.parse().unwrap_or(1000))
+			.max(10); // The minimum cache size is 10
+		Mutex::new(LruCache::new(NonZeroUsize::new(cache_size).unwrap()))
+	});
+	let key = (query.to_owned(), case_sensitive);
+	let mut cache = match FUZZY_CACHE.lock() {
+		Ok(guard) => guard,
+		Err(poisoned) => poisoned.into_inner(),
+	};
+	if let Some(q) = cache.get(&key) {
+		return q.clone();
+	}
+	let chars: Vec<char> = if case_sensitive {
+		query.chars().collect()
+	} else {
+		query.to_lowercase().chars().collect()
+	};
+	let compiled = FuzzyQuery {
+		chars,
+		case_sensitive,
+	};
+	cache.put(key, compiled.clone());
+	compiled
+}
+
+/// Returns `true` when every character of `query` appears in `haystack`, in
+/// order, allowing arbitrary gaps. This is the cheap prefilter nucleo/fzf run
+/// before paying for the full dynamic-programming score: most haystacks
+/// reject here and never reach the scoring matrix.
+fn is_subsequence(haystack: &[char], query: &[char]) -> bool {
+	let mut q = query.iter();
+	let Some(mut next) = q.next() else {
+		return true;
+	};
+	for c in haystack {
+		if c == next {
+			match q.next() {
+				Some(n) => next = n,
+				None => return true,
+			}
+		}
+	}
+	false
+}
+
+/// Checks word-boundary status against `haystack`'s original casing: the
+/// lower->upper camelCase transition only exists before the haystack is
+/// lower-cased for case-insensitive matching, so callers must pass the
+/// original-cased chars here even when matching case-insensitively.
+fn is_boundary(haystack: &[char], idx: usize) -> bool {
+	if idx == 0 {
+		return true;
+	}
+	let prev = haystack[idx - 1];
+	let cur = haystack[idx];
+	if prev == '_' || prev == '-' || prev == ' ' || prev == '.' || prev == '/' {
+		return true;
+	}
+	prev.is_lowercase() && cur.is_uppercase()
+}
+
+/// Fuzzy-matches `query` against `haystack`, returning the score (divided by
+/// the query length) and the indices in `haystack` that were matched, or
+/// `None` if `query` is not a subsequence of `haystack`.
+///
+/// The score is computed with a Smith-Waterman-style dynamic-programming
+/// pass, the same approach used by fzf/nucleo: each cell holds the best
+/// score of matching the first `i` query characters within the first `j`
+/// haystack characters, whether or not the last of them is matched at `j`,
+/// plus a bonus carried over for consecutive runs.
+pub fn fuzzy_match(haystack: &str, query: &str, case_sensitive: bool) -> Option<(i64, Vec<usize>)> {
+	let compiled = fuzzy_new(query, case_sensitive);
+	if compiled.chars.is_empty() {
+		return Some((0, Vec::new()));
+	}
+	// Kept in its original casing so `is_boundary` can still see lower->upper
+	// camelCase transitions; `haystack_chars` (possibly lower-cased) is what
+	// actually gets compared against the query.
+	let haystack_orig: Vec<char> = haystack.chars().collect();
+	let haystack_chars: Vec<char> = if case_sensitive {
+		haystack_orig.clone()
+	} else {
+		haystack.to_lowercase().chars().collect()
+	};
+
+	if !is_subsequence(&haystack_chars, &compiled.chars) {
+		return None;
+	}
+
+	let n = compiled.chars.len();
+	let m = haystack_chars.len();
+
+	// score[i][j]: best score matching query[..i] within haystack[..j], with
+	// query[i-1] matched at haystack[j-1] or earlier. consecutive[i][j]: length of the consecutive-match run
+	// ending there, used to grow BONUS_CONSECUTIVE. last_pos[i][j]: haystack
+	// index of that match, used to size the gap penalty for the next one.
+	// matched[i][j]: whether the best score at this cell ends in a match at
+	// j-1, used to recover the matched indices by tracing the DP back.
+	const NEG: i64 = i64::MIN / 4;
+	let mut score = vec![vec![NEG; m + 1]; n + 1];
+	let mut consecutive = vec![vec![0usize; m + 1]; n + 1];
+	let mut last_pos = vec![vec![0usize; m + 1]; n + 1];
+	let mut matched = vec![vec![false; m + 1]; n + 1];
+
+	for i in 1..=n {
+		let qc = compiled.chars[i - 1];
+		for j in 1..=m {
+			// Best score achievable for query[..i] using only the first j
+			// haystack characters (matched or not): either skip haystack[j-1]
+			// and carry the previous best forward, or match it here.
+			let mut best_here = score[i][j - 1];
+			let mut best_consecutive = consecutive[i][j - 1];
+			let mut best_last_pos = last_pos[i][j - 1];
+			let mut best_matched = false;
+
+			if haystack_chars[j - 1] == qc {
+				let prev_best = if i == 1 {
+					0
+				} else {
+					score[i - 1][j - 1]
+				};
+				if prev_best > NEG {
+					let boundary = is_boundary(&haystack_orig, j - 1);
+					let mut bonus = SCORE_MATCH;
+					if boundary {
+						bonus += BONUS_BOUNDARY;
+						if j == 1 {
+							bonus += BONUS_FIRST_CHAR;
+						}
+					}
+					let prev_last_pos = if i == 1 {
+						0
+					} else {
+						last_pos[i - 1][j - 1]
+					};
+					let run = if i > 1 && prev_last_pos + 1 == j - 1 {
+						consecutive[i - 1][j - 1] + 1
+					} else {
+						1
+					};
+					bonus += BONUS_CONSECUTIVE * run.min(8) as i64;
+
+					// Gap penalty: charge for haystack characters skipped
+					// since the previous match, growing with the gap length.
+					let gap = if i == 1 {
+						j - 1
+					} else {
+						(j - 1).saturating_sub(prev_last_pos + 1)
+					};
+					let gap_penalty = if gap > 0 {
+						PENALTY_GAP + PENALTY_GAP_EXTRA * (gap as i64 - 1)
+					} else {
+						0
+					};
+
+					let candidate = prev_best + bonus - gap_penalty;
+					if candidate > best_here {
+						best_here = candidate;
+						best_consecutive = run;
+						best_last_pos = j - 1;
+						best_matched = true;
+					}
+				}
+			}
+
+			score[i][j] = best_here;
+			consecutive[i][j] = best_consecutive;
+			last_pos[i][j] = best_last_pos;
+			matched[i][j] = best_matched;
+		}
+	}
+
+	let best = score[n][m];
+	if best <= NEG {
+		return None;
+	}
+
+	// Normalize by query length so scores are comparable across queries.
+	let normalized = best / n as i64;
+
+	Some((normalized, traceback_indices(n, m, &matched)))
+}
+
+/// Recovers the matched haystack indices by tracing the DP's `matched` grid
+/// back from `(n, m)`: at each cell either the best score came from a match
+/// at `j - 1` (recorded, then step diagonally) or it was carried forward
+/// unmatched from `j - 1`. This reconstructs the exact alignment the score
+/// was computed from, rather than an independent leftmost-subsequence walk
+/// that could disagree with it (e.g. when a query character repeats).
+fn traceback_indices(n: usize, m: usize, matched: &[Vec<bool>]) -> Vec<usize> {
+	let mut indices = vec![0usize; n];
+	let mut i = n;
+	let mut j = m;
+	while i > 0 && j > 0 {
+		if matched[i][j] {
+			indices[i - 1] = j - 1;
+			i -= 1;
+			j -= 1;
+		} else {
+			j -= 1;
+		}
+	}
+	indices
+}
+
+/// Returns `true` when the fuzzy score of `query` against `haystack` meets
+/// `threshold`; backs the `~~` boolean SQL operator.
+pub fn fuzzy_is_match(haystack: &str, query: &str, case_sensitive: bool, threshold: i64) -> bool {
+	match fuzzy_match(haystack, query, case_sensitive) {
+		Some((score, _)) => score >= threshold,
+		None => false,
+	}
+}
+
+/// Returns the fuzzy score as computed by [`fuzzy_match`] (divided by the
+/// query length), or `None` if `query` does not match at all; backs
+/// `ORDER BY` ranking.
+pub fn fuzzy_score(haystack: &str, query: &str, case_sensitive: bool) -> Option<i64> {
+	fuzzy_match(haystack, query, case_sensitive).map(|(score, _)| score)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_out_of_order_query() {
+		assert_eq!(fuzzy_match("hello", "oh", false), None);
+	}
+
+	#[test]
+	fn matches_subsequence() {
+		assert!(fuzzy_match("hello world", "hlwrd", false).is_some());
+	}
+
+	#[test]
+	fn case_insensitive_by_default() {
+		assert!(fuzzy_match("Hello World", "hw", false).is_some());
+		assert_eq!(fuzzy_match("Hello World", "hw", true), None);
+	}
+
+	#[test]
+	fn word_boundary_scores_higher_than_mid_word() {
+		let (boundary_score, _) = fuzzy_match("foo_bar", "b", false).unwrap();
+		let (mid_word_score, _) = fuzzy_match("foobar", "b", false).unwrap();
+		assert!(boundary_score > mid_word_score);
+	}
+
+	#[test]
+	fn camel_case_boundary_is_recognised() {
+		let (score, _) = fuzzy_match("fooBar", "b", false).unwrap();
+		let (no_boundary, _) = fuzzy_match("foobar", "b", false).unwrap();
+		assert!(score > no_boundary);
+	}
+
+	#[test]
+	fn consecutive_matches_score_higher_than_scattered() {
+		let (consecutive, _) = fuzzy_match("abcdef", "abc", false).unwrap();
+		let (scattered, _) = fuzzy_match("a_b_c_def", "abc", false).unwrap();
+		assert!(consecutive > scattered);
+	}
+
+	#[test]
+	fn empty_query_matches_everything() {
+		assert_eq!(fuzzy_match("anything", "", false), Some((0, Vec::new())));
+	}
+
+	#[test]
+	fn is_match_respects_threshold() {
+		assert!(fuzzy_is_match("hello", "hello", false, 10));
+		assert!(!fuzzy_is_match("hello", "hello", false, 1_000_000));
+	}
+}
@@ -0,0 +1,56 @@
+use crate::idx::trees::vector::Vector;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+
+/// The distance metric an `MTree` index is built with.
+///
+/// Any future `MTree` pruning (skipping whole subtrees using a node's
+/// covering radius) relies on the triangle inequality holding for the
+/// metric, so every variant documents whether it is a proper metric or not.
+/// `MTree` does not yet have routing objects or covering radii to prune
+/// with, so today every variant is scanned exhaustively regardless of
+/// `is_metric` — see `MTree::knn_search`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub enum Distance {
+	#[default]
+	Euclidean,
+	/// `1 - cosine_similarity(a, b)` computed over L2-normalized vectors.
+	/// This is a proper metric (it is equivalent to half the squared
+	/// Euclidean distance between unit vectors), as long as every stored and
+	/// queried vector is normalized first.
+	Cosine,
+	/// The raw (negated) dot product. Not a metric: it does not satisfy the
+	/// triangle inequality, so it could never be pruned with covering radii
+	/// even once `MTree` grows them.
+	DotProduct,
+}
+
+impl Distance {
+	/// Whether this metric satisfies the triangle inequality, and so could
+	/// be pruned with `MTree` covering radii once `MTree` has routing
+	/// objects to prune with. `MTree` does not yet, so this flag is not
+	/// currently consulted by `insert`/`knn_search`.
+	pub fn is_metric(&self) -> bool {
+		!matches!(self, Distance::DotProduct)
+	}
+
+	/// Vectors must be L2-normalized before being compared with this metric.
+	pub fn requires_normalization(&self) -> bool {
+		matches!(self, Distance::Cosine)
+	}
+
+	/// Computes the distance between two vectors of equal dimension.
+	///
+	/// For `Cosine`, `a` and `b` are assumed to already be normalized (the
+	/// caller is responsible for normalizing on insert and at query time, so
+	/// normalization only ever happens once per vector, not once per
+	/// comparison).
+	pub fn calculate(&self, a: &Vector, b: &Vector) -> f64 {
+		match self {
+			Distance::Euclidean => a.euclidean_distance(b),
+			Distance::Cosine => 1.0 - a.dot(b),
+			Distance::DotProduct => -a.dot(b),
+		}
+	}
+}
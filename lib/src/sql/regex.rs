@@ -6,6 +6,7 @@ use serde::{
 	Deserialize, Deserializer, Serialize, Serializer,
 };
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::fmt::{self, Display, Formatter};
 use std::hash::{Hash, Hasher};
@@ -25,6 +26,38 @@ impl Regex {
 	pub fn regex(&self) -> &regex::Regex {
 		&self.0
 	}
+
+	/// Returns every full match of this pattern in `haystack`, in order.
+	/// Used by the SQL function `string::matches` (see `fnc::string::matches`).
+	pub fn matches(&self, haystack: &str) -> Vec<String> {
+		self.0.find_iter(haystack).map(|m| m.as_str().to_owned()).collect()
+	}
+
+	/// Returns the capture groups of every match of this pattern in
+	/// `haystack`, in order. Each match is keyed first by its numbered
+	/// groups (`"0"` is the whole match, `"1"` the first parenthesized
+	/// group, and so on), then by any named groups (`(?P<name>...)`).
+	/// A group that didn't participate in a particular match (e.g. one side
+	/// of an alternation) is `None` rather than an empty string, so
+	/// `fnc::string::captures` can map it to `Value::Null`.
+	///
+	/// Used by the SQL function `string::captures` (see `fnc::string::captures`).
+	pub fn captures(&self, haystack: &str) -> Vec<BTreeMap<String, Option<String>>> {
+		self.0
+			.captures_iter(haystack)
+			.map(|caps| {
+				let mut groups = BTreeMap::new();
+				for (i, name) in self.0.capture_names().enumerate() {
+					let value = caps.get(i).map(|m| m.as_str().to_owned());
+					groups.insert(i.to_string(), value.clone());
+					if let Some(name) = name {
+						groups.insert(name.to_owned(), value);
+					}
+				}
+				groups
+			})
+			.collect()
+	}
 }
 
 fn regex_new(str: &str) -> Result<regex::Regex, regex::Error> {